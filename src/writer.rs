@@ -21,6 +21,12 @@ pub enum WriterError {
     MissingEndOfFileRecord,
     /// Object contains multiple EoF records.
     MultipleEndOfFileRecords(usize),
+    /// Two `Data` records describe overlapping address ranges. Fields are the absolute
+    /// start addresses of the first and second (in record order) conflicting records.
+    OverlappingData { first: u32, second: u32 },
+    /// A `Data` record's absolute address falls behind the highest address reached by an
+    /// earlier record, without actually overlapping it.
+    AddressRegression { previous: u32, next: u32 },
 }
 
 impl Error for WriterError {
@@ -35,6 +41,12 @@ impl Error for WriterError {
             &WriterError::MultipleEndOfFileRecords(_) => {
                 "Object files must contain exactle one End of File record."
             }
+            &WriterError::OverlappingData { .. } => {
+                "Object file contains overlapping Data records."
+            }
+            &WriterError::AddressRegression { .. } => {
+                "Object file contains a Data record that regresses the address space."
+            }
         }
     }
 }
@@ -194,3 +206,738 @@ pub fn create_object_file_representation(records: &[Record]) -> Result<String, W
         .collect::<Result<Vec<String>, WriterError>>()
         .map(|list| list.join("\n"))
 }
+
+///
+/// Chunks a flat binary image into a sequence of `Data` records starting at `start_address`,
+/// inserting `ExtendedLinearAddress` records whenever the high 16 bits of the address change,
+/// and terminating the stream with an `EndOfFile` record. No `Data` record will straddle a
+/// 64 KiB address boundary, since the record offset is only 16 bits wide.
+///
+/// # Example
+///
+/// ```rust
+/// use ihex::writer;
+///
+/// let data = vec![0x48, 0x65, 0x6C, 0x6C, 0x6F];
+/// let records = writer::records_for_binary(0x0000, &data, 16);
+/// ```
+///
+pub fn records_for_binary(
+    start_address: u32,
+    data: &[u8],
+    bytes_per_record: usize,
+) -> Vec<Record> {
+    assert!(bytes_per_record > 0, "bytes_per_record must be non-zero");
+
+    let mut records = Vec::new();
+    let mut current_address = start_address;
+    let mut last_set_ela: u16 = 0;
+    let mut remaining = data;
+
+    while !remaining.is_empty() {
+        let hi = (current_address >> 16) as u16;
+        let lo = (current_address & 0xFFFF) as u16;
+
+        if hi != last_set_ela {
+            records.push(Record::ExtendedLinearAddress(hi));
+            last_set_ela = hi;
+        }
+
+        // Never let a Data record cross the 64 KiB boundary implied by the 16-bit offset.
+        let room_in_segment = 0x10000 - lo as usize;
+        let chunk_len = bytes_per_record.min(room_in_segment).min(remaining.len());
+
+        let (chunk, rest) = remaining.split_at(chunk_len);
+        records.push(Record::Data {
+            offset: lo,
+            value: chunk.to_vec(),
+        });
+
+        current_address = current_address.wrapping_add(chunk_len as u32);
+        remaining = rest;
+    }
+
+    records.push(Record::EndOfFile);
+    records
+}
+
+///
+/// A contiguous range of memory reconstructed from one or more `Data` records, spanning
+/// `[start, end)` within the 32-bit address space.
+///
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct MemoryRegion {
+    pub start: u32,
+    pub end: u32,
+    pub buffer: Vec<u8>,
+}
+
+///
+/// Reconstructs the sparse memory image described by `records`, the inverse of
+/// `create_object_file_representation`. The absolute address of each `Data` record is formed
+/// from the 32-bit base established by the most recent `ExtendedLinearAddress`
+/// (`base = address << 16`) or `ExtendedSegmentAddress` (`base = address << 4`) record, plus
+/// the record's own 16-bit offset. Data that is contiguous or overlapping is coalesced into a
+/// single `MemoryRegion`; an address gap starts a new one. Where a later record overlaps an
+/// earlier one already folded into a region, the later record's bytes win.
+///
+/// # Example
+///
+/// ```rust
+/// use ihex::record::Record;
+/// use ihex::writer;
+///
+/// let records = &[
+///   Record::Data { offset: 0x0010, value: vec![0x48,0x65,0x6C,0x6C,0x6F] },
+///   Record::EndOfFile
+/// ];
+///
+/// let regions = writer::flatten(records);
+/// ```
+///
+pub fn flatten(records: &[Record]) -> Result<Vec<MemoryRegion>, WriterError> {
+    let mut regions: Vec<MemoryRegion> = Vec::new();
+    let mut base: u32 = 0;
+
+    for record in records {
+        match record {
+            &Record::ExtendedLinearAddress(address) => {
+                base = (address as u32) << 16;
+            }
+
+            &Record::ExtendedSegmentAddress(address) => {
+                base = (address as u32) << 4;
+            }
+
+            &Record::Data { offset, ref value } => {
+                let start = base + offset as u32;
+                let end = start + value.len() as u32;
+
+                // Find every existing region this record touches (overlaps or is adjacent
+                // to), not just the most recently pushed one, so that a record overlapping
+                // an earlier region is folded in rather than left to describe the same
+                // addresses as a separate, conflicting MemoryRegion.
+                let touched: Vec<usize> = regions
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, region)| start <= region.end && end >= region.start)
+                    .map(|(i, _)| i)
+                    .collect();
+
+                if touched.is_empty() {
+                    regions.push(MemoryRegion {
+                        start,
+                        end,
+                        buffer: value.clone(),
+                    });
+                } else {
+                    let merged_start = touched
+                        .iter()
+                        .map(|&i| regions[i].start)
+                        .fold(start, u32::min);
+                    let merged_end = touched
+                        .iter()
+                        .map(|&i| regions[i].end)
+                        .fold(end, u32::max);
+
+                    let mut buffer = vec![0u8; (merged_end - merged_start) as usize];
+                    for &i in &touched {
+                        let region = &regions[i];
+                        let region_offset = (region.start - merged_start) as usize;
+                        buffer[region_offset..region_offset + region.buffer.len()]
+                            .copy_from_slice(&region.buffer);
+                    }
+
+                    // The new record is the most recent write, so it wins over whatever was
+                    // already folded into the touched regions.
+                    let overwrite_offset = (start - merged_start) as usize;
+                    buffer[overwrite_offset..overwrite_offset + value.len()]
+                        .copy_from_slice(value);
+
+                    for &i in touched.iter().rev() {
+                        regions.remove(i);
+                    }
+                    regions.push(MemoryRegion {
+                        start: merged_start,
+                        end: merged_end,
+                        buffer,
+                    });
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(regions)
+}
+
+///
+/// Flattens `records` into a single contiguous `Vec<u8>` spanning the lowest to highest
+/// address touched by any `Data` record, filling gaps between regions with `fill`. Returns an
+/// empty buffer if `records` contains no `Data` records.
+///
+pub fn flatten_to_binary(records: &[Record], fill: u8) -> Result<Vec<u8>, WriterError> {
+    let regions = flatten(records)?;
+
+    let start = match regions.iter().map(|region| region.start).min() {
+        Some(start) => start,
+        None => return Ok(Vec::new()),
+    };
+    let end = regions.iter().map(|region| region.end).max().unwrap();
+
+    let mut buffer = vec![fill; (end - start) as usize];
+    for region in &regions {
+        let offset = (region.start - start) as usize;
+        buffer[offset..offset + region.buffer.len()].copy_from_slice(&region.buffer);
+    }
+
+    Ok(buffer)
+}
+
+///
+/// Behaves exactly like `create_object_file_representation`, except that `Data` records longer
+/// than `max_len` bytes are transparently split into a sequence of `Data` records of at most
+/// `max_len` bytes each, rather than causing `WriterError::DataExceedsMaximumLength`. Offsets
+/// are incremented across the split, and a fresh `ExtendedLinearAddress` record is inserted
+/// whenever a split crosses a 64 KiB boundary; after such a split the record that set the
+/// previous address base is emitted again. `max_len` must be in `1..=255`; `0` and values
+/// above 255 (the IHEX record data limit) both yield `WriterError::DataExceedsMaximumLength`.
+///
+/// # Example
+///
+/// ```rust
+/// use ihex::record::Record;
+/// use ihex::writer;
+///
+/// let records = &[
+///   Record::Data { offset: 0x0000, value: vec![0u8; 512] },
+///   Record::EndOfFile
+/// ];
+///
+/// let result = writer::create_object_file_representation_with_max_len(records, 255);
+/// ```
+///
+pub fn create_object_file_representation_with_max_len(
+    records: &[Record],
+    max_len: usize,
+) -> Result<String, WriterError> {
+    if max_len == 0 || max_len > 0xFF {
+        return Err(WriterError::DataExceedsMaximumLength(max_len));
+    }
+
+    let split_records = split_oversized_data_records(records, max_len);
+    create_object_file_representation(&split_records)
+}
+
+///
+/// Serializes `records` into a record-preserving firmware image, mirroring the ihex2fw
+/// conversion used to build Linux kernel firmware blobs. Each `Data` record is written as a
+/// big-endian `{ address: u32, length: u16 }` header, with the absolute address reconstructed
+/// from the active `ExtendedLinearAddress`/`ExtendedSegmentAddress` base, followed by the raw
+/// data bytes. The blocks are concatenated in record order and the image is terminated by a
+/// zero-length sentinel block.
+///
+/// # Example
+///
+/// ```rust
+/// use ihex::record::Record;
+/// use ihex::writer;
+///
+/// let records = &[
+///   Record::Data { offset: 0x0010, value: vec![0x48,0x65,0x6C,0x6C,0x6F] },
+///   Record::EndOfFile
+/// ];
+///
+/// let result = writer::create_firmware_image(records);
+/// ```
+///
+pub fn create_firmware_image(records: &[Record]) -> Result<Vec<u8>, WriterError> {
+    let mut image = Vec::new();
+    let mut base: u32 = 0;
+
+    for record in records {
+        match record {
+            &Record::ExtendedLinearAddress(address) => {
+                base = (address as u32) << 16;
+            }
+
+            &Record::ExtendedSegmentAddress(address) => {
+                base = (address as u32) << 4;
+            }
+
+            &Record::Data { offset, ref value } => {
+                if value.len() > 0xFFFF {
+                    return Err(WriterError::DataExceedsMaximumLength(value.len()));
+                }
+
+                let address = base + offset as u32;
+                let length = value.len() as u16;
+
+                image.push(((address & 0xFF000000) >> 24) as u8);
+                image.push(((address & 0x00FF0000) >> 16) as u8);
+                image.push(((address & 0x0000FF00) >> 8) as u8);
+                image.push(((address & 0x000000FF) >> 0) as u8);
+                image.push(((length & 0xFF00) >> 8) as u8);
+                image.push(((length & 0x00FF) >> 0) as u8);
+                image.extend_from_slice(value);
+            }
+
+            _ => {}
+        }
+    }
+
+    // Zero-length sentinel block terminating the image.
+    image.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+
+    Ok(image)
+}
+
+/// Rewrites `records`, replacing any `Data` record longer than `max_len` bytes with a sequence
+/// of `Data` records of at most `max_len` bytes, inserting `ExtendedLinearAddress` records as
+/// needed to keep each resulting record's offset within its 64 KiB segment.
+fn split_oversized_data_records(records: &[Record], max_len: usize) -> Vec<Record> {
+    let mut result = Vec::with_capacity(records.len());
+    let mut base: u32 = 0;
+    // The record that established `base`, re-emitted after a split that had to insert its own
+    // ExtendedLinearAddress records.
+    let mut base_record = Record::ExtendedLinearAddress(0);
+
+    for record in records {
+        match record {
+            &Record::ExtendedLinearAddress(address) => {
+                base = (address as u32) << 16;
+                base_record = record.clone();
+                result.push(record.clone());
+            }
+
+            &Record::ExtendedSegmentAddress(address) => {
+                base = (address as u32) << 4;
+                base_record = record.clone();
+                result.push(record.clone());
+            }
+
+            &Record::Data { offset, ref value } if value.len() > max_len => {
+                let mut current_address = base + offset as u32;
+                let mut last_set_ela = (base >> 16) as u16;
+                // The active base is only 64 KiB-aligned once we've emitted our own
+                // ExtendedLinearAddress; until then it may be an arbitrary
+                // ExtendedSegmentAddress base, so offsets must be computed relative to it
+                // rather than via `current_address & 0xFFFF`.
+                let mut segment_base = base;
+                let mut remaining = value.as_slice();
+
+                while !remaining.is_empty() {
+                    let hi = (current_address >> 16) as u16;
+
+                    if hi != last_set_ela {
+                        result.push(Record::ExtendedLinearAddress(hi));
+                        last_set_ela = hi;
+                        segment_base = (hi as u32) << 16;
+                    }
+
+                    let rel_offset = current_address - segment_base;
+                    let room_in_segment = 0x10000 - rel_offset as usize;
+                    let chunk_len = max_len.min(room_in_segment).min(remaining.len());
+                    let (chunk, rest) = remaining.split_at(chunk_len);
+
+                    result.push(Record::Data {
+                        offset: rel_offset as u16,
+                        value: chunk.to_vec(),
+                    });
+
+                    current_address = current_address.wrapping_add(chunk_len as u32);
+                    remaining = rest;
+                }
+
+                // An ExtendedLinearAddress pushed above changes the base for every record that
+                // follows. Re-emit the record that set the original base, so that later records
+                // keep the absolute addresses they have in `records`; `base` stays as it is.
+                if segment_base != base {
+                    result.push(base_record.clone());
+                }
+            }
+
+            _ => result.push(record.clone()),
+        }
+    }
+
+    result
+}
+
+///
+/// Behaves exactly like `create_object_file_representation`, but additionally verifies that
+/// the `Data` records describe a consistent memory map: no two records' absolute address
+/// ranges may overlap, and no record may fall behind the highest address reached by an earlier
+/// record. Absolute addresses are reconstructed from the active `ExtendedLinearAddress`/
+/// `ExtendedSegmentAddress` base, exactly as in `flatten`. Callers that intentionally rely on
+/// later-wins overwrite semantics should continue to use `create_object_file_representation`
+/// instead.
+///
+/// # Example
+///
+/// ```rust
+/// use ihex::record::Record;
+/// use ihex::writer;
+///
+/// let records = &[
+///   Record::Data { offset: 0x0010, value: vec![0x48,0x65,0x6C,0x6C,0x6F] },
+///   Record::EndOfFile
+/// ];
+///
+/// let result = writer::create_object_file_representation_with_validation(records);
+/// ```
+///
+pub fn create_object_file_representation_with_validation(
+    records: &[Record],
+) -> Result<String, WriterError> {
+    validate_address_space(records)?;
+    create_object_file_representation(records)
+}
+
+/// Walks `records`, reconstructing absolute addresses, and verifies that no two `Data` records
+/// overlap and that none regresses behind the highest address reached so far.
+fn validate_address_space(records: &[Record]) -> Result<(), WriterError> {
+    let mut base: u32 = 0;
+    let mut intervals: Vec<(u32, u32)> = Vec::new();
+    let mut high_water_mark: u32 = 0;
+
+    for record in records {
+        match record {
+            &Record::ExtendedLinearAddress(address) => {
+                base = (address as u32) << 16;
+            }
+
+            &Record::ExtendedSegmentAddress(address) => {
+                base = (address as u32) << 4;
+            }
+
+            &Record::Data { offset, ref value } => {
+                let start = base + offset as u32;
+                let end = start + value.len() as u32;
+
+                for &(other_start, other_end) in &intervals {
+                    if start < other_end && other_start < end {
+                        return Err(WriterError::OverlappingData {
+                            first: other_start,
+                            second: start,
+                        });
+                    }
+                }
+
+                if start < high_water_mark {
+                    return Err(WriterError::AddressRegression {
+                        previous: high_water_mark,
+                        next: start,
+                    });
+                }
+
+                high_water_mark = high_water_mark.max(end);
+                intervals.push((start, end));
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_for_binary_splits_at_64kib_boundary() {
+        let data = vec![0xAAu8; 8];
+        let records = records_for_binary(0xFFFC, &data, 16);
+
+        assert_eq!(
+            records,
+            vec![
+                Record::Data {
+                    offset: 0xFFFC,
+                    value: vec![0xAA; 4],
+                },
+                Record::ExtendedLinearAddress(0x0001),
+                Record::Data {
+                    offset: 0x0000,
+                    value: vec![0xAA; 4],
+                },
+                Record::EndOfFile,
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn records_for_binary_rejects_zero_bytes_per_record() {
+        records_for_binary(0x0000, &[0x00], 0);
+    }
+
+    #[test]
+    fn flatten_starts_a_new_region_for_a_disjoint_earlier_range() {
+        let records = &[
+            Record::Data {
+                offset: 100,
+                value: vec![1u8; 10],
+            },
+            Record::Data {
+                offset: 50,
+                value: vec![2u8; 10],
+            },
+            Record::EndOfFile,
+        ];
+
+        let regions = flatten(records).unwrap();
+
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0], MemoryRegion {
+            start: 100,
+            end: 110,
+            buffer: vec![1u8; 10],
+        });
+        assert_eq!(regions[1], MemoryRegion {
+            start: 50,
+            end: 60,
+            buffer: vec![2u8; 10],
+        });
+    }
+
+    #[test]
+    fn flatten_merges_a_record_that_overlaps_a_non_last_region() {
+        let records = &[
+            Record::Data {
+                offset: 100,
+                value: vec![1u8; 10],
+            },
+            Record::Data {
+                offset: 200,
+                value: vec![2u8; 10],
+            },
+            Record::Data {
+                offset: 105,
+                value: vec![9u8; 4],
+            },
+            Record::EndOfFile,
+        ];
+
+        let regions = flatten(records).unwrap();
+
+        assert_eq!(regions.len(), 2);
+
+        let mut expected_merged = vec![1u8; 10];
+        expected_merged[5..9].copy_from_slice(&[9u8; 4]);
+
+        let merged = regions
+            .iter()
+            .find(|region| region.start == 100)
+            .expect("region covering the first write should still exist");
+        assert_eq!(merged.end, 110);
+        assert_eq!(merged.buffer, expected_merged);
+
+        let untouched = regions
+            .iter()
+            .find(|region| region.start == 200)
+            .expect("region covering the unrelated write should be untouched");
+        assert_eq!(untouched.end, 210);
+        assert_eq!(untouched.buffer, vec![2u8; 10]);
+    }
+
+    #[test]
+    fn flatten_overwrites_overlapping_bytes_with_later_record() {
+        let records = &[
+            Record::Data {
+                offset: 0,
+                value: vec![1u8; 20],
+            },
+            Record::Data {
+                offset: 5,
+                value: vec![9u8; 5],
+            },
+            Record::EndOfFile,
+        ];
+
+        let regions = flatten(records).unwrap();
+
+        let mut expected = vec![1u8; 20];
+        expected[5..10].copy_from_slice(&[9u8; 5]);
+
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].buffer, expected);
+    }
+
+    #[test]
+    fn flatten_roundtrips_through_extended_segment_address() {
+        let records = &[
+            Record::ExtendedSegmentAddress(0x0010),
+            Record::Data {
+                offset: 0,
+                value: vec![0xAA; 10],
+            },
+            Record::EndOfFile,
+        ];
+
+        let regions = flatten(records).unwrap();
+
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].start, 0x100);
+        assert_eq!(regions[0].end, 0x10A);
+    }
+
+    #[test]
+    fn split_oversized_data_records_computes_offsets_relative_to_esa_base() {
+        let records = &[
+            Record::ExtendedSegmentAddress(0x0010),
+            Record::Data {
+                offset: 0,
+                value: vec![0xAA; 10],
+            },
+            Record::EndOfFile,
+        ];
+
+        let split = split_oversized_data_records(records, 4);
+        let regions = flatten(&split).unwrap();
+
+        // base = 0x0010 << 4 = 0x100; splitting must not double-count that base.
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].start, 0x100);
+        assert_eq!(regions[0].end, 0x10A);
+        assert_eq!(regions[0].buffer, vec![0xAA; 10]);
+    }
+
+    #[test]
+    fn split_oversized_data_records_keeps_unaligned_esa_base_across_records() {
+        let records = &[
+            Record::ExtendedSegmentAddress(0x1001),
+            Record::Data {
+                offset: 0,
+                value: vec![0x11; 10],
+            },
+            Record::Data {
+                offset: 10,
+                value: vec![0x22; 16],
+            },
+            Record::EndOfFile,
+        ];
+
+        let split = split_oversized_data_records(records, 8);
+        let regions = flatten(&split).unwrap();
+
+        // base = 0x1001 << 4 = 0x10010, an address that is not 64 KiB-aligned; neither
+        // record crosses a page, so no fresh ExtendedLinearAddress should ever be emitted
+        // and the unaligned base must survive across both split records unchanged.
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].start, 0x10010);
+        assert_eq!(regions[0].end, 0x10010 + 10 + 16);
+
+        let mut expected = vec![0x11u8; 10];
+        expected.extend(vec![0x22u8; 16]);
+        assert_eq!(regions[0].buffer, expected);
+    }
+
+    #[test]
+    fn create_object_file_representation_with_max_len_rejects_zero() {
+        let records = &[
+            Record::Data {
+                offset: 0,
+                value: vec![0u8; 4],
+            },
+            Record::EndOfFile,
+        ];
+
+        let result = create_object_file_representation_with_max_len(records, 0);
+        assert_eq!(result, Err(WriterError::DataExceedsMaximumLength(0)));
+    }
+
+    #[test]
+    fn create_firmware_image_reconstructs_address_from_ela_and_terminates_with_sentinel() {
+        let records = &[
+            Record::ExtendedLinearAddress(0x0001),
+            Record::Data {
+                offset: 0x0010,
+                value: vec![0x48, 0x65, 0x6C, 0x6C, 0x6F],
+            },
+            Record::EndOfFile,
+        ];
+
+        let image = create_firmware_image(records).unwrap();
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&[0x00, 0x01, 0x00, 0x10]); // address 0x00010010
+        expected.extend_from_slice(&[0x00, 0x05]); // length 5
+        expected.extend_from_slice(&[0x48, 0x65, 0x6C, 0x6C, 0x6F]);
+        expected.extend_from_slice(&[0, 0, 0, 0, 0, 0]); // sentinel
+
+        assert_eq!(image, expected);
+    }
+
+    #[test]
+    fn validation_rejects_overlapping_data_records() {
+        let records = &[
+            Record::Data {
+                offset: 0,
+                value: vec![0u8; 10],
+            },
+            Record::Data {
+                offset: 5,
+                value: vec![0u8; 10],
+            },
+            Record::EndOfFile,
+        ];
+
+        let result = create_object_file_representation_with_validation(records);
+        assert_eq!(
+            result,
+            Err(WriterError::OverlappingData {
+                first: 0,
+                second: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn validation_rejects_address_regression_without_overlap() {
+        let records = &[
+            Record::Data {
+                offset: 100,
+                value: vec![0u8; 10],
+            },
+            Record::Data {
+                offset: 200,
+                value: vec![0u8; 10],
+            },
+            Record::Data {
+                offset: 150,
+                value: vec![0u8; 10],
+            },
+            Record::EndOfFile,
+        ];
+
+        let result = create_object_file_representation_with_validation(records);
+        assert_eq!(
+            result,
+            Err(WriterError::AddressRegression {
+                previous: 210,
+                next: 150,
+            })
+        );
+    }
+
+    #[test]
+    fn validation_accepts_contiguous_non_overlapping_data() {
+        let records = &[
+            Record::Data {
+                offset: 0,
+                value: vec![0u8; 10],
+            },
+            Record::Data {
+                offset: 10,
+                value: vec![0u8; 10],
+            },
+            Record::EndOfFile,
+        ];
+
+        assert!(create_object_file_representation_with_validation(records).is_ok());
+    }
+}